@@ -5,7 +5,142 @@ use image::{GenericImage, ImageBuffer, Pixel};
 use std::f32;
 
 use crate::pixelops::weighted_sum;
-use ab_glyph::{point, Font, Glyph, Point, PxScale, ScaleFont};
+use ab_glyph::{point, Font, Glyph, GlyphId, Point, PxScale, Rect, ScaleFont};
+
+#[cfg(feature = "rustybuzz-shaping")]
+use unicode_bidi::BidiInfo;
+
+/// Controls how [`draw_text_shaped_mut`] lays out a paragraph.
+///
+/// When the `rustybuzz-shaping` feature is enabled this routes text through a
+/// HarfBuzz-compatible shaper: the Unicode Bidirectional Algorithm splits the
+/// paragraph into directional runs, and each run is shaped with `rustybuzz`
+/// (applying GSUB substitutions for ligatures/contextual forms and GPOS
+/// positioning/kerning) before being laid out left-to-right or right-to-left.
+/// Without the feature this falls back to [`layout_paragraph`].
+#[cfg(feature = "rustybuzz-shaping")]
+#[derive(Clone, Copy)]
+pub struct ShapingOptions<'f> {
+    /// Raw font file bytes. `rustybuzz::Face` borrows from the source bytes
+    /// directly, rather than from the `ab_glyph::Font` used for rasterization,
+    /// so both must be supplied and must refer to the same font.
+    pub font_data: &'f [u8],
+}
+
+/// Lays out `text` as directional, shaped runs into `target`, mirroring bracket
+/// glyphs in right-to-left runs and resetting the caret on `\n` per run.
+///
+/// Falls back to [`layout_paragraph`] when the `rustybuzz-shaping` feature is
+/// disabled or the font data fails to load as a `rustybuzz::Face`.
+#[cfg(feature = "rustybuzz-shaping")]
+pub fn layout_paragraph_shaped<F, SF>(
+    font: SF,
+    options: ShapingOptions,
+    position: Point,
+    text: &str,
+    target: &mut Vec<Glyph>,
+) where
+    F: Font,
+    SF: ScaleFont<F>,
+{
+    let face = match rustybuzz::Face::from_slice(options.font_data, 0) {
+        Some(face) => face,
+        None => {
+            layout_paragraph(font, position, text, target);
+            return;
+        }
+    };
+
+    let v_advance = font.height() + font.line_gap();
+    let mut caret = position + point(0.0, font.ascent());
+
+    for line in text.split_inclusive('\n') {
+        let (line, has_newline) = match line.strip_suffix('\n') {
+            Some(stripped) => (stripped, true),
+            None => (line, false),
+        };
+
+        let bidi_info = BidiInfo::new(line, None);
+        for para in &bidi_info.paragraphs {
+            let line_range = para.range.clone();
+            let (levels, runs) = bidi_info.visual_runs(para, line_range);
+            for run in runs {
+                let rtl = levels[run.start].is_rtl();
+                shape_run(&font, &face, &line[run.clone()], rtl, &mut caret, target);
+            }
+        }
+
+        if has_newline {
+            caret = point(position.x, caret.y + v_advance);
+        }
+    }
+}
+
+#[cfg(feature = "rustybuzz-shaping")]
+fn shape_run<F, SF>(
+    font: &SF,
+    face: &rustybuzz::Face,
+    run: &str,
+    rtl: bool,
+    caret: &mut Point,
+    target: &mut Vec<Glyph>,
+) where
+    F: Font,
+    SF: ScaleFont<F>,
+{
+    // Mirror bracket glyphs (e.g. `(` -> `)`) so they read correctly in RTL runs.
+    let shaped_text: std::borrow::Cow<str> = if rtl {
+        run.chars()
+            .map(|c| unicode_bidi_mirroring::get_mirrored(c).unwrap_or(c))
+            .collect::<String>()
+            .into()
+    } else {
+        run.into()
+    };
+
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(&shaped_text);
+    buffer.set_direction(if rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
+
+    let glyph_buffer = rustybuzz::shape(face, &[], buffer);
+    let infos = glyph_buffer.glyph_infos();
+    let positions = glyph_buffer.glyph_positions();
+
+    // rustybuzz reports advances/offsets in font units at the face's upem; scale
+    // them down to the `ab_glyph` pixel scale used for rasterization.
+    let units_per_em = face.units_per_em() as f32;
+    let (px_per_unit_x, px_per_unit_y) = rustybuzz_unit_to_px_scale(font.scale(), units_per_em);
+
+    for (info, pos) in infos.iter().zip(positions.iter()) {
+        let position = *caret
+            + point(
+                pos.x_offset as f32 * px_per_unit_x,
+                -(pos.y_offset as f32) * px_per_unit_y,
+            );
+        target.push(Glyph {
+            id: GlyphId(info.glyph_id as u16),
+            scale: font.scale(),
+            position,
+        });
+
+        caret.x += pos.x_advance as f32 * px_per_unit_x;
+        caret.y -= pos.y_advance as f32 * px_per_unit_y;
+    }
+}
+
+/// Converts a rustybuzz font-unit-per-em scale into the `(x, y)` pixel
+/// scale factors used to convert shaped offsets/advances, which are reported
+/// in font units at the face's upem, into `ab_glyph` pixel space. The x and
+/// y axes are scaled independently since `PxScale` itself allows a
+/// non-uniform x/y pixel scale (e.g. condensed or stretched text).
+#[cfg(feature = "rustybuzz-shaping")]
+fn rustybuzz_unit_to_px_scale(px_scale: PxScale, units_per_em: f32) -> (f32, f32) {
+    (px_scale.x / units_per_em, px_scale.y / units_per_em)
+}
 
 /// Simple paragraph layout for glyphs into `target`.
 /// Taken from https://github.com/alexheretic/ab-glyph/blob/master/dev/src/layout.rs
@@ -38,7 +173,7 @@ where
     }
 }
 
-/// Draws colored text on an image in place. `scale` is augmented font scaling on both the x and y axis (in pixels). Note that this function *does not* support newlines, you must do this manually
+/// Draws colored text on an image in place. `scale` is augmented font scaling on both the x and y axis (in pixels). Note that this function *does not* support newlines, you must do this manually. Glyphs with no vector outline (e.g. color emoji) are skipped; use [`draw_text_with_emoji_mut`] to render those too.
 pub fn draw_text_mut<'a, C, F>(
     canvas: &'a mut C,
     color: C::Pixel,
@@ -58,8 +193,50 @@ pub fn draw_text_mut<'a, C, F>(
     let position = point(x as f32, y as f32);
     layout_paragraph(scaled_font, position, text, &mut glyphs);
 
-    let last_glyph = &glyphs[glyphs.len() - 1];
-    let actual_width = last_glyph.position.x + last_glyph.scale.x;
+    let actual_width = glyphs_bounding_box(&scaled_font, &glyphs).width();
+    if actual_width > max_width as f32 {
+        let shrink_factor = actual_width / (max_width as f32);
+        let new_scale = PxScale {
+            x: scale.x / shrink_factor,
+            y: scale.y,
+        };
+        glyphs.clear();
+        let rescaled_font = font.as_scaled(new_scale);
+        layout_paragraph(rescaled_font, position, text, &mut glyphs);
+    }
+
+    draw_glyphs(canvas, color, scaled_font, glyphs);
+}
+
+/// Draws colored text on an image in place, like [`draw_text_mut`], but also
+/// renders embedded bitmap/color-emoji glyphs via `Font::glyph_raster_image2`
+/// for glyphs that have no vector outline.
+///
+/// This is a separate function rather than a widened bound on
+/// [`draw_text_mut`] because decoding and blending a raster glyph's RGBA
+/// pixels onto the canvas requires `C::Pixel: From<image::Rgba<u8>>`, which
+/// not every `Canvas` pixel type implements.
+pub fn draw_text_with_emoji_mut<'a, C, F>(
+    canvas: &'a mut C,
+    color: C::Pixel,
+    x: u32,
+    y: u32,
+    scale: PxScale,
+    max_width: u32,
+    font: F,
+    text: &'a str,
+) where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    C::Pixel: From<image::Rgba<u8>>,
+    F: Font,
+{
+    let scaled_font = font.as_scaled(scale);
+    let mut glyphs = Vec::new();
+    let position = point(x as f32, y as f32);
+    layout_paragraph(scaled_font, position, text, &mut glyphs);
+
+    let actual_width = glyphs_bounding_box(&scaled_font, &glyphs).width();
     if actual_width > max_width as f32 {
         let shrink_factor = actual_width / (max_width as f32);
         let new_scale = PxScale {
@@ -71,9 +248,103 @@ pub fn draw_text_mut<'a, C, F>(
         layout_paragraph(rescaled_font, position, text, &mut glyphs);
     }
 
+    draw_glyphs_with_raster(canvas, color, scaled_font, glyphs);
+}
+
+/// Computes the bounding box (in the same coordinate space as the glyph
+/// positions) enclosing every glyph's rendered extent, whether it's a vector
+/// outline or a raster glyph (e.g. color emoji) painted via
+/// [`draw_glyphs_with_raster`]/[`paint_raster_glyph`], matching the area
+/// actually painted by either.
+fn glyphs_bounding_box<F, SF>(scaled_font: &SF, glyphs: &[Glyph]) -> Rect
+where
+    F: Font,
+    SF: ScaleFont<F>,
+{
+    let mut bbox: Option<Rect> = None;
+    let mut grow = |bounds: Rect| {
+        bbox = Some(match bbox {
+            Some(b) => Rect {
+                min: point(b.min.x.min(bounds.min.x), b.min.y.min(bounds.min.y)),
+                max: point(b.max.x.max(bounds.max.x), b.max.y.max(bounds.max.y)),
+            },
+            None => bounds,
+        });
+    };
+    for glyph in glyphs {
+        if let Some(outlined) = scaled_font.outline_glyph(glyph.clone()) {
+            grow(outlined.px_bounds());
+        } else if let Some(bounds) = raster_glyph_bounds(scaled_font.font(), glyph) {
+            grow(bounds);
+        }
+    }
+    bbox.unwrap_or(Rect {
+        min: point(0.0, 0.0),
+        max: point(0.0, 0.0),
+    })
+}
+
+/// A raster glyph (e.g. color emoji), decoded and resized to the pixel size
+/// it's actually painted at, together with its canvas-space origin.
+///
+/// `width`/`height` on `ab_glyph::v2::GlyphImage` are only the font's
+/// *declared* size and aren't guaranteed to match the decoded image's real
+/// dimensions (notably for PNG-backed glyphs), so [`raster_glyph_bounds`] and
+/// [`paint_raster_glyph`] both measure/paint from this same decoded bitmap
+/// instead of trusting two different size sources that could disagree.
+struct DecodedRasterGlyph {
+    image: image::RgbaImage,
+    origin: Point,
+}
+
+fn decode_raster_glyph<F: Font>(font: &F, glyph: &Glyph) -> Option<DecodedRasterGlyph> {
+    let raster = font.glyph_raster_image2(glyph.id, glyph.scale.y as u16)?;
+    let decoded = image::load_from_memory(raster.data).ok()?.to_rgba8();
+
+    // Embedded bitmaps are stored at their own `pixels_per_em`; rescale to the
+    // requested glyph scale.
+    let px_scale = glyph.scale.y / raster.pixels_per_em as f32;
+    let target_width = ((decoded.width() as f32) * px_scale).round().max(1.0) as u32;
+    let target_height = ((decoded.height() as f32) * px_scale).round().max(1.0) as u32;
+    let resized = image::imageops::resize(
+        &decoded,
+        target_width,
+        target_height,
+        image::imageops::FilterType::Triangle,
+    );
+
+    let origin_x = glyph.position.x + raster.origin.x * px_scale;
+    let origin_y = glyph.position.y - raster.origin.y * px_scale;
+
+    Some(DecodedRasterGlyph {
+        image: resized,
+        origin: point(origin_x, origin_y),
+    })
+}
+
+/// Computes the pixel-space extent a raster glyph (e.g. color emoji) will
+/// occupy when painted by [`paint_raster_glyph`].
+fn raster_glyph_bounds<F: Font>(font: &F, glyph: &Glyph) -> Option<Rect> {
+    let decoded = decode_raster_glyph(font, glyph)?;
+    Some(Rect {
+        min: decoded.origin,
+        max: point(
+            decoded.origin.x + decoded.image.width() as f32,
+            decoded.origin.y + decoded.image.height() as f32,
+        ),
+    })
+}
+
+fn draw_glyphs<C, F, SF>(canvas: &mut C, color: C::Pixel, scaled_font: SF, glyphs: Vec<Glyph>)
+where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    F: Font,
+    SF: ScaleFont<F>,
+{
     // Loop through the glyphs in the text, positing each one on a line
     for glyph in glyphs {
-        if let Some(outlined) = scaled_font.outline_glyph(glyph) {
+        if let Some(outlined) = scaled_font.outline_glyph(glyph.clone()) {
             let bounds = outlined.px_bounds();
             // Draw the glyph into the image per-pixel by using the draw closure
             outlined.draw(|x, y, v| {
@@ -89,7 +360,90 @@ pub fn draw_text_mut<'a, C, F>(
     }
 }
 
-/// Draws colored text on an image in place. `scale` is augmented font scaling on both the x and y axis (in pixels). Note that this function *does not* support newlines, you must do this manually
+/// Like [`draw_glyphs`], but also renders glyphs with no vector outline (e.g.
+/// color emoji) via `Font::glyph_raster_image2`.
+fn draw_glyphs_with_raster<C, F, SF>(canvas: &mut C, color: C::Pixel, scaled_font: SF, glyphs: Vec<Glyph>)
+where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    C::Pixel: From<image::Rgba<u8>>,
+    F: Font,
+    SF: ScaleFont<F>,
+{
+    for glyph in glyphs {
+        if let Some(outlined) = scaled_font.outline_glyph(glyph.clone()) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|x, y, v| {
+                let pixel_x = x + bounds.min.x as u32;
+                let pixel_y = y + bounds.min.y as u32;
+                let px = canvas.get_pixel(pixel_x, pixel_y);
+                let weighted_color = weighted_sum(px, color, 1.0 - v, v);
+                canvas.draw_pixel(pixel_x, pixel_y, weighted_color);
+            });
+        } else if let Some(decoded) = decode_raster_glyph(scaled_font.font(), &glyph) {
+            paint_raster_glyph(canvas, &decoded);
+        }
+    }
+}
+
+/// Alpha-composites an already-decoded, already-resized raster glyph (e.g.
+/// color emoji) onto `canvas`, blending it with [`weighted_sum`] like an
+/// outlined glyph.
+fn paint_raster_glyph<C>(canvas: &mut C, decoded: &DecodedRasterGlyph)
+where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    C::Pixel: From<image::Rgba<u8>>,
+{
+    for (dx, dy, rgba) in decoded.image.enumerate_pixels() {
+        let alpha = rgba[3] as f32 / 255.0;
+        if alpha == 0.0 {
+            continue;
+        }
+        let pixel_x = decoded.origin.x + dx as f32;
+        let pixel_y = decoded.origin.y + dy as f32;
+        if pixel_x < 0.0 || pixel_y < 0.0 {
+            continue;
+        }
+        let (pixel_x, pixel_y) = (pixel_x as u32, pixel_y as u32);
+        let src_color = C::Pixel::from(*rgba);
+        let dst_color = canvas.get_pixel(pixel_x, pixel_y);
+        let blended = weighted_sum(dst_color, src_color, 1.0 - alpha, alpha);
+        canvas.draw_pixel(pixel_x, pixel_y, blended);
+    }
+}
+
+/// Draws colored text on an image in place, shaping it with a HarfBuzz-style
+/// shaper (bidi splitting, GSUB ligatures/contextual forms, GPOS positioning)
+/// instead of the simple per-codepoint layout used by [`draw_text_mut`].
+///
+/// Requires the `rustybuzz-shaping` feature; `options.font_data` must be the
+/// raw bytes of the same font passed as `font`.
+#[cfg(feature = "rustybuzz-shaping")]
+pub fn draw_text_shaped_mut<'a, C, F>(
+    canvas: &'a mut C,
+    color: C::Pixel,
+    x: u32,
+    y: u32,
+    scale: PxScale,
+    options: ShapingOptions,
+    font: F,
+    text: &'a str,
+) where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    C::Pixel: From<image::Rgba<u8>>,
+    F: Font,
+{
+    let scaled_font = font.as_scaled(scale);
+    let mut glyphs = Vec::new();
+    let position = point(x as f32, y as f32);
+    layout_paragraph_shaped(scaled_font, options, position, text, &mut glyphs);
+
+    draw_glyphs_with_raster(canvas, color, scaled_font, glyphs);
+}
+
+/// Draws colored text on an image in place. `scale` is augmented font scaling on both the x and y axis (in pixels). Note that this function *does not* support newlines, you must do this manually. Glyphs with no vector outline (e.g. color emoji) are skipped; use [`draw_text_with_emoji`] to render those too.
 pub fn draw_text<'a, I, F>(
     image: &'a mut I,
     color: I::Pixel,
@@ -111,3 +465,894 @@ where
     draw_text_mut(&mut out, color, x, y, scale, max_width, font, text);
     out
 }
+
+/// Draws colored text on an image in place like [`draw_text`], but also
+/// renders embedded bitmap/color-emoji glyphs via [`draw_text_with_emoji_mut`].
+pub fn draw_text_with_emoji<'a, I, F>(
+    image: &'a mut I,
+    color: I::Pixel,
+    x: u32,
+    y: u32,
+    scale: PxScale,
+    max_width: u32,
+    font: F,
+    text: &'a str,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    <I::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    I::Pixel: From<image::Rgba<u8>>,
+    I::Pixel: 'static,
+    F: Font,
+{
+    let mut out = ImageBuffer::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_text_with_emoji_mut(&mut out, color, x, y, scale, max_width, font, text);
+    out
+}
+
+/// Lays out `text` at `position` and returns the glyphs together with the
+/// bounding box (computed as the union of each glyph's `px_bounds`) that
+/// encloses them.
+pub fn layout_text<F: Font>(
+    scale: PxScale,
+    position: Point,
+    font: &F,
+    text: &str,
+) -> (Vec<Glyph>, Rect) {
+    let scaled_font = font.as_scaled(scale);
+    let mut glyphs = Vec::new();
+    layout_paragraph(scaled_font, position, text, &mut glyphs);
+    let bbox = glyphs_bounding_box(&scaled_font, &glyphs);
+    (glyphs, bbox)
+}
+
+/// Returns the `(width, height)` in pixels that `text` would occupy if drawn
+/// with [`draw_text_mut`] at the given `scale`, letting callers compute
+/// centering/alignment offsets before drawing.
+pub fn text_size<F: Font>(scale: PxScale, font: &F, text: &str) -> (i32, i32) {
+    let (_, bbox) = layout_text(scale, point(0.0, 0.0), font, text);
+    (bbox.width().round() as i32, bbox.height().round() as i32)
+}
+
+/// Horizontal anchor point for [`draw_text_anchored_mut`], mirroring CSS
+/// `text-align`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HorizontalAnchor {
+    Left,
+    Center,
+    Right,
+}
+
+/// Vertical anchor point for [`draw_text_anchored_mut`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalAnchor {
+    Top,
+    Middle,
+    Baseline,
+    Bottom,
+}
+
+/// Draws colored text on an image in place like [`draw_text_with_emoji_mut`],
+/// but `(x, y)` denotes the anchor point given by `h_align`/`v_align` instead
+/// of always being the top-left corner. This lets callers center or
+/// right-align text, or anchor it to its baseline, without first calling
+/// [`text_size`] themselves.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_anchored_mut<'a, C, F>(
+    canvas: &'a mut C,
+    color: C::Pixel,
+    x: u32,
+    y: u32,
+    h_align: HorizontalAnchor,
+    v_align: VerticalAnchor,
+    scale: PxScale,
+    max_width: u32,
+    font: F,
+    text: &'a str,
+) where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    C::Pixel: From<image::Rgba<u8>>,
+    F: Font + Copy,
+{
+    let (_, bbox) = layout_text(scale, point(0.0, 0.0), &font, text);
+    let scaled_font = font.as_scaled(scale);
+
+    let origin_x = match h_align {
+        HorizontalAnchor::Left => x as f32,
+        HorizontalAnchor::Center => x as f32 - bbox.width() / 2.0,
+        HorizontalAnchor::Right => x as f32 - bbox.width(),
+    };
+    // `bbox` is measured in the same coordinate space `layout_text` laid the
+    // glyphs out in (position.y = 0, i.e. relative to the ascent-based
+    // caret), so its painted ink spans `bbox.min.y..bbox.max.y`, not
+    // `0.0..bbox.height()` -- real glyphs sit well below that caret (e.g. an
+    // "x" has a positive `min.y`). Middle/Bottom must subtract `bbox.min.y`
+    // (equivalently, anchor off `bbox.max.y`) to land on the actual ink
+    // instead of being off by that offset.
+    let origin_y = match v_align {
+        VerticalAnchor::Top => y as f32,
+        VerticalAnchor::Middle => y as f32 - (bbox.min.y + bbox.max.y) / 2.0,
+        VerticalAnchor::Baseline => y as f32 - scaled_font.ascent(),
+        VerticalAnchor::Bottom => y as f32 - bbox.max.y,
+    };
+
+    draw_text_with_emoji_mut(
+        canvas,
+        color,
+        origin_x.max(0.0).round() as u32,
+        origin_y.max(0.0).round() as u32,
+        scale,
+        max_width,
+        font,
+        text,
+    );
+}
+
+/// Number of sub-pixel bins [`GlyphCache`] quantizes the horizontal glyph
+/// offset into before looking up a cached rasterization. The vertical offset
+/// is rounded to the nearest whole pixel instead of binned, since baseline
+/// jitter of a pixel is far less visible than horizontal spacing artifacts.
+const GLYPH_CACHE_SUBPIXEL_BINS_X: u8 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphCacheKey {
+    font_fingerprint: u64,
+    glyph_id: GlyphId,
+    subpixel_bin_x: u8,
+    scale_bits: (u32, u32),
+}
+
+/// A cheap, content-based stand-in for font identity, so a single
+/// [`GlyphCache`] can safely be shared across multiple fonts (e.g. a regular
+/// and a bold face used together for batch labeling) without glyph ids from
+/// one font colliding with another's cache entries. Built from metrics every
+/// `Font` impl already exposes, rather than the font's address, since
+/// `draw_text_cached_mut` takes `font: F` by value and a by-value font can
+/// legitimately live at a different address on every call.
+fn font_fingerprint<F: Font>(font: &F) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    font.units_per_em().map(f32::to_bits).hash(&mut hasher);
+    font.ascent_unscaled().to_bits().hash(&mut hasher);
+    font.descent_unscaled().to_bits().hash(&mut hasher);
+    font.line_gap_unscaled().to_bits().hash(&mut hasher);
+    font.glyph_count().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A rasterized glyph's coverage bitmap, plus the offset from the quantized
+/// caret position to the bitmap's top-left pixel.
+struct CachedGlyph {
+    coverage: Vec<u8>,
+    width: u32,
+    height: u32,
+    bearing_x: i32,
+    bearing_y: i32,
+}
+
+fn quantize_caret(position: Point) -> (i32, i32, u8) {
+    let x_floor = position.x.floor();
+    let subpixel = position.x - x_floor;
+    let bin = ((subpixel * GLYPH_CACHE_SUBPIXEL_BINS_X as f32) as u8)
+        .min(GLYPH_CACHE_SUBPIXEL_BINS_X - 1);
+    (x_floor as i32, position.y.round() as i32, bin)
+}
+
+fn rasterize_glyph<F, SF>(scaled_font: &SF, glyph_id: GlyphId, scale: PxScale, bin: u8) -> Option<CachedGlyph>
+where
+    F: Font,
+    SF: ScaleFont<F>,
+{
+    let subpixel_x = bin as f32 / GLYPH_CACHE_SUBPIXEL_BINS_X as f32;
+    let glyph = Glyph {
+        id: glyph_id,
+        scale,
+        position: point(subpixel_x, 0.0),
+    };
+    let outlined = scaled_font.outline_glyph(glyph)?;
+    let bounds = outlined.px_bounds();
+    let width = bounds.width() as u32;
+    let height = bounds.height() as u32;
+    let mut coverage = vec![0u8; (width * height) as usize];
+    outlined.draw(|x, y, v| {
+        coverage[(y * width + x) as usize] = (v.clamp(0.0, 1.0) * 255.0) as u8;
+    });
+    Some(CachedGlyph {
+        coverage,
+        width,
+        height,
+        bearing_x: bounds.min.x as i32,
+        bearing_y: bounds.min.y as i32,
+    })
+}
+
+fn blit_cached_glyph<C>(canvas: &mut C, color: C::Pixel, x_floor: i32, y_rounded: i32, cached: &CachedGlyph)
+where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+{
+    for row in 0..cached.height {
+        for col in 0..cached.width {
+            let v = cached.coverage[(row * cached.width + col) as usize];
+            if v == 0 {
+                continue;
+            }
+            let pixel_x = x_floor + cached.bearing_x + col as i32;
+            let pixel_y = y_rounded + cached.bearing_y + row as i32;
+            if pixel_x < 0 || pixel_y < 0 {
+                continue;
+            }
+            let (pixel_x, pixel_y) = (pixel_x as u32, pixel_y as u32);
+            let alpha = v as f32 / 255.0;
+            let px = canvas.get_pixel(pixel_x, pixel_y);
+            let weighted_color = weighted_sum(px, color, 1.0 - alpha, alpha);
+            canvas.draw_pixel(pixel_x, pixel_y, weighted_color);
+        }
+    }
+}
+
+/// An LRU cache of rasterized glyph coverage bitmaps, keyed by a font
+/// fingerprint, glyph id, quantized sub-pixel offset and font scale. A single
+/// cache can safely be shared across multiple fonts (e.g. labeling with a
+/// regular and a bold face) or reused across calls at different scales; each
+/// combination gets its own entries. Speeds up repeated text drawing (e.g.
+/// frame-by-frame overlays, batch labeling) by rasterizing each distinct
+/// glyph at most once per cache eviction cycle instead of re-running
+/// `outline_glyph`/`Outline::draw` on every call.
+///
+/// Sub-pixel offsets are quantized into [`GLYPH_CACHE_SUBPIXEL_BINS_X`] bins
+/// so that nearby x positions share a cache entry, trading a small amount of
+/// positioning accuracy for a much higher hit rate.
+pub struct GlyphCache {
+    cache: lru::LruCache<GlyphCacheKey, Option<CachedGlyph>>,
+}
+
+impl GlyphCache {
+    /// Creates a cache holding at most `capacity` rasterized glyphs.
+    pub fn new(capacity: usize) -> Self {
+        GlyphCache {
+            cache: lru::LruCache::new(std::num::NonZeroUsize::new(capacity.max(1)).unwrap()),
+        }
+    }
+
+    /// Draws `text` like [`draw_text_mut`], blitting cached rasterizations
+    /// where available and rasterizing (then caching) on a miss.
+    pub fn draw_text_cached_mut<C, F>(
+        &mut self,
+        canvas: &mut C,
+        color: C::Pixel,
+        x: u32,
+        y: u32,
+        scale: PxScale,
+        max_width: u32,
+        font: F,
+        text: &str,
+    ) where
+        C: Canvas,
+        <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+        F: Font,
+    {
+        let mut scale = scale;
+        let scaled_font = font.as_scaled(scale);
+        let mut glyphs = Vec::new();
+        let position = point(x as f32, y as f32);
+        layout_paragraph(scaled_font, position, text, &mut glyphs);
+
+        let actual_width = glyphs_bounding_box(&scaled_font, &glyphs).width();
+        if actual_width > max_width as f32 {
+            let shrink_factor = actual_width / (max_width as f32);
+            scale = PxScale {
+                x: scale.x / shrink_factor,
+                y: scale.y,
+            };
+            glyphs.clear();
+            let rescaled_font = font.as_scaled(scale);
+            layout_paragraph(rescaled_font, position, text, &mut glyphs);
+        }
+
+        let scaled_font = font.as_scaled(scale);
+        let scale_bits = (scale.x.to_bits(), scale.y.to_bits());
+        let font_fingerprint = font_fingerprint(scaled_font.font());
+        for glyph in &glyphs {
+            let (x_floor, y_rounded, bin) = quantize_caret(glyph.position);
+            let key = GlyphCacheKey {
+                font_fingerprint,
+                glyph_id: glyph.id,
+                subpixel_bin_x: bin,
+                scale_bits,
+            };
+            let cached = self
+                .cache
+                .get_or_insert(key, || rasterize_glyph(&scaled_font, glyph.id, scale, bin));
+            if let Some(cached) = cached {
+                blit_cached_glyph(canvas, color, x_floor, y_rounded, cached);
+            }
+        }
+    }
+}
+
+/// Options for [`draw_text_block_mut`].
+#[derive(Debug, Clone, Copy)]
+pub struct TextBlockOptions {
+    /// Multiplier applied on top of `font.height() + font.line_gap()` when
+    /// advancing the caret to the next line, letting callers loosen or
+    /// tighten line spacing without changing the font scale.
+    pub line_height: f32,
+}
+
+impl Default for TextBlockOptions {
+    fn default() -> Self {
+        TextBlockOptions { line_height: 1.0 }
+    }
+}
+
+/// Paragraph layout like [`layout_paragraph`], but word-wraps lines at
+/// `max_width`: whenever a word would cross `max_width`, the glyphs typed
+/// since the last whitespace are shifted down to a new line instead of being
+/// split mid-word. A word still on its own line is allowed to overflow
+/// rather than being broken further.
+pub fn layout_paragraph_wrapped<F, SF>(
+    font: SF,
+    position: Point,
+    max_width: f32,
+    line_height: f32,
+    text: &str,
+    target: &mut Vec<Glyph>,
+) where
+    F: Font,
+    SF: ScaleFont<F>,
+{
+    let v_advance = (font.height() + font.line_gap()) * line_height;
+    let mut caret = position + point(0.0, font.ascent());
+    let mut last_glyph: Option<Glyph> = None;
+    // Index into `target` of the first glyph on the current line, and of the
+    // glyph right after the most recent whitespace (the start of the word
+    // currently being typed), together with the caret position there.
+    let mut line_start = target.len();
+    let mut word_start = (target.len(), caret);
+
+    for c in text.chars() {
+        if c.is_control() {
+            if c == '\n' {
+                caret = point(position.x, caret.y + v_advance);
+                last_glyph = None;
+                line_start = target.len();
+                word_start = (target.len(), caret);
+            }
+            continue;
+        }
+
+        let mut glyph = font.scaled_glyph(c);
+        if let Some(previous) = last_glyph.take() {
+            caret.x += font.kern(previous.id, glyph.id);
+        }
+        glyph.position = caret;
+        last_glyph = Some(glyph.clone());
+        caret.x += font.h_advance(glyph.id);
+        target.push(glyph);
+
+        if c.is_whitespace() {
+            word_start = (target.len(), caret);
+        } else if caret.x - position.x > max_width && word_start.0 > line_start {
+            let (break_index, word_caret) = word_start;
+            let dx = position.x - word_caret.x;
+            let dy = v_advance;
+            for g in &mut target[break_index..] {
+                g.position.x += dx;
+                g.position.y += dy;
+            }
+            caret.x += dx;
+            caret.y += dy;
+            line_start = break_index;
+            word_start = (break_index, point(position.x, word_caret.y + dy));
+        }
+    }
+}
+
+/// Draws word-wrapped, multi-line colored text on an image in place,
+/// respecting `\n` and wrapping at `max_width`, and returns the rendered
+/// block's bounding box (the union of every drawn glyph's `px_bounds`).
+///
+/// Unlike [`draw_text_mut`], which documents newlines as unsupported, this
+/// treats `text` as a full paragraph: use it instead of manually splitting
+/// strings and computing per-line baselines.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_block_mut<'a, C, F>(
+    canvas: &'a mut C,
+    color: C::Pixel,
+    x: u32,
+    y: u32,
+    scale: PxScale,
+    max_width: u32,
+    options: TextBlockOptions,
+    font: F,
+    text: &'a str,
+) -> Rect
+where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: ValueInto<f32> + Clamp<f32>,
+    C::Pixel: From<image::Rgba<u8>>,
+    F: Font,
+{
+    let scaled_font = font.as_scaled(scale);
+    let mut glyphs = Vec::new();
+    let position = point(x as f32, y as f32);
+    layout_paragraph_wrapped(
+        scaled_font,
+        position,
+        max_width as f32,
+        options.line_height,
+        text,
+        &mut glyphs,
+    );
+
+    let bbox = glyphs_bounding_box(&scaled_font, &glyphs);
+    draw_glyphs_with_raster(canvas, color, scaled_font, glyphs);
+    bbox
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ab_glyph::FontRef;
+
+    fn test_font() -> FontRef<'static> {
+        let data: &[u8] = include_bytes!("../../tests/data/fonts/DejaVuSans.ttf");
+        FontRef::try_from_slice(data).unwrap()
+    }
+
+    fn test_font_bold() -> FontRef<'static> {
+        let data: &[u8] = include_bytes!("../../tests/data/fonts/DejaVuSans-Bold.ttf");
+        FontRef::try_from_slice(data).unwrap()
+    }
+
+    /// Encodes a solid-color `width`x`height` RGBA bitmap as PNG bytes, for use
+    /// as a synthetic raster glyph in [`build_raster_test_font`].
+    fn encode_solid_rgba_png(width: u32, height: u32, rgba: [u8; 4]) -> Vec<u8> {
+        use image::ImageEncoder;
+
+        let image = image::RgbaImage::from_fn(width, height, |_, _| image::Rgba(rgba));
+        let mut png_data = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png_data)
+            .write_image(&image, width, height, image::ColorType::Rgba8)
+            .unwrap();
+        png_data
+    }
+
+    /// Hand-assembles a minimal valid TrueType font with a single `sbix`
+    /// color-bitmap glyph (id 1) embedding `png_data`, for exercising the
+    /// decode/resize/paint path that a real color-emoji font would hit.
+    ///
+    /// None of the font fixtures checked into `tests/data/fonts/` contain a
+    /// bitmap glyph, and there's no such font available to vendor here, so we
+    /// synthesize the few mandatory tables (`head`, `hhea`, `maxp`) plus
+    /// `sbix` by hand instead.
+    fn build_raster_test_font(png_data: &[u8], units_per_em: u16, pixels_per_em: u16) -> Vec<u8> {
+        const NUM_GLYPHS: u16 = 2; // glyph 0 (.notdef, empty) + glyph 1 (raster).
+
+        let mut head = vec![0u8; 54];
+        head[18..20].copy_from_slice(&units_per_em.to_be_bytes());
+        // x_min/y_min/x_max/y_max (bytes 36..44) left as zero; unused here.
+        head[50..52].copy_from_slice(&0u16.to_be_bytes()); // index_to_location_format: short
+
+        let mut hhea = vec![0u8; 36];
+        hhea[4..6].copy_from_slice(&(units_per_em as i16).to_be_bytes()); // ascender
+        hhea[34..36].copy_from_slice(&0u16.to_be_bytes()); // number_of_metrics
+
+        let mut maxp = vec![0u8; 6];
+        maxp[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        maxp[4..6].copy_from_slice(&NUM_GLYPHS.to_be_bytes());
+
+        // `sbix` strike: header (pixels_per_em, ppi, offsets[NUM_GLYPHS + 1])
+        // followed by each glyph's data. Glyph 0 is empty (its start == end);
+        // glyph 1 holds the embedded PNG.
+        let strike_header_len = 2 + 2 + 4 * (NUM_GLYPHS as usize + 1);
+        let glyph1_offset = strike_header_len as u32;
+        let glyph1_header_len = 2 + 2 + 4; // x: i16, y: i16, image_type: Tag
+        let glyph1_len = glyph1_header_len + png_data.len();
+        let strike_end = glyph1_offset + glyph1_len as u32;
+
+        let mut strike = Vec::new();
+        strike.extend_from_slice(&pixels_per_em.to_be_bytes());
+        strike.extend_from_slice(&0u16.to_be_bytes()); // ppi
+        strike.extend_from_slice(&glyph1_offset.to_be_bytes()); // glyph 0 start == end: empty
+        strike.extend_from_slice(&glyph1_offset.to_be_bytes()); // glyph 1 start
+        strike.extend_from_slice(&strike_end.to_be_bytes()); // end marker
+        strike.extend_from_slice(&0i16.to_be_bytes()); // glyph 1 x
+        strike.extend_from_slice(&0i16.to_be_bytes()); // glyph 1 y
+        strike.extend_from_slice(b"png ");
+        strike.extend_from_slice(png_data);
+
+        let sbix_header_len = 2 + 2 + 4 + 4; // version, flags, strikesCount, one strike offset
+        let mut sbix = Vec::new();
+        sbix.extend_from_slice(&1u16.to_be_bytes()); // version
+        sbix.extend_from_slice(&0u16.to_be_bytes()); // flags
+        sbix.extend_from_slice(&1u32.to_be_bytes()); // strikesCount
+        sbix.extend_from_slice(&(sbix_header_len as u32).to_be_bytes()); // strike offset
+        sbix.extend_from_slice(&strike);
+
+        let tables: [(&[u8; 4], &[u8]); 4] =
+            [(b"head", &head), (b"hhea", &hhea), (b"maxp", &maxp), (b"sbix", &sbix)];
+
+        const SFNT_HEADER_LEN: usize = 12;
+        const TABLE_RECORD_LEN: usize = 16;
+        let mut offset = SFNT_HEADER_LEN + TABLE_RECORD_LEN * tables.len();
+
+        let mut font = Vec::new();
+        font.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // sfnt version
+        font.extend_from_slice(&(tables.len() as u16).to_be_bytes()); // numTables
+        font.extend_from_slice(&0u16.to_be_bytes()); // searchRange
+        font.extend_from_slice(&0u16.to_be_bytes()); // entrySelector
+        font.extend_from_slice(&0u16.to_be_bytes()); // rangeShift
+
+        for (tag, data) in &tables {
+            font.extend_from_slice(*tag);
+            font.extend_from_slice(&0u32.to_be_bytes()); // checksum (unverified by ttf-parser)
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in &tables {
+            font.extend_from_slice(data);
+        }
+        font
+    }
+
+    #[cfg(feature = "rustybuzz-shaping")]
+    #[test]
+    fn rustybuzz_unit_to_px_scale_scales_axes_independently() {
+        let (x, y) = rustybuzz_unit_to_px_scale(PxScale { x: 40.0, y: 20.0 }, 1000.0);
+        assert_eq!(x, 0.04);
+        assert_eq!(y, 0.02);
+    }
+
+    #[cfg(feature = "rustybuzz-shaping")]
+    #[test]
+    fn layout_paragraph_shaped_lays_out_ltr_text_left_to_right() {
+        let font = test_font();
+        let options = ShapingOptions {
+            font_data: include_bytes!("../../tests/data/fonts/DejaVuSans.ttf"),
+        };
+        let scaled_font = font.as_scaled(PxScale::from(20.0));
+        let mut glyphs = Vec::new();
+        layout_paragraph_shaped(scaled_font, options, point(0.0, 0.0), "abc", &mut glyphs);
+
+        assert_eq!(glyphs.len(), 3);
+        assert!(glyphs.windows(2).all(|w| w[1].position.x > w[0].position.x));
+    }
+
+    #[cfg(feature = "rustybuzz-shaping")]
+    #[test]
+    fn layout_paragraph_shaped_handles_rtl_runs() {
+        let font = test_font();
+        let options = ShapingOptions {
+            font_data: include_bytes!("../../tests/data/fonts/DejaVuSans.ttf"),
+        };
+        let scaled_font = font.as_scaled(PxScale::from(20.0));
+
+        // A Hebrew run doesn't crash the bidi/rustybuzz pipeline and produces
+        // one glyph per letter, each at a distinct caret position.
+        let mut glyphs = Vec::new();
+        layout_paragraph_shaped(scaled_font, options, point(0.0, 0.0), "\u{5d0}\u{5d1}\u{5d2}", &mut glyphs);
+
+        assert_eq!(glyphs.len(), 3);
+        assert!(glyphs.windows(2).all(|w| w[1].position.x != w[0].position.x));
+    }
+
+    #[test]
+    fn draw_text_mut_accepts_canvases_with_no_rgba_conversion() {
+        // `Luma<u8>` doesn't implement `From<image::Rgba<u8>>`; this is a
+        // compile-time regression check that `draw_text_mut` doesn't require
+        // that conversion (unlike `draw_text_with_emoji_mut`, which does).
+        use image::{GrayImage, Luma};
+
+        let font = test_font();
+        let mut image = GrayImage::new(60, 20);
+        draw_text_mut(
+            &mut image,
+            Luma([255u8]),
+            0,
+            0,
+            PxScale::from(16.0),
+            60,
+            &font,
+            "Hi",
+        );
+
+        assert!(image.pixels().any(|p| p.0[0] > 0));
+    }
+
+    #[test]
+    fn draw_text_with_emoji_mut_renders_outline_glyphs_like_draw_text_mut() {
+        use image::{Rgba, RgbaImage};
+
+        let font = test_font();
+        let scale = PxScale::from(16.0);
+
+        let mut plain = RgbaImage::new(60, 20);
+        draw_text_mut(&mut plain, Rgba([255u8, 0, 0, 255]), 0, 0, scale, 60, &font, "Hi");
+
+        let mut with_emoji = RgbaImage::new(60, 20);
+        draw_text_with_emoji_mut(
+            &mut with_emoji,
+            Rgba([255u8, 0, 0, 255]),
+            0,
+            0,
+            scale,
+            60,
+            &font,
+            "Hi",
+        );
+
+        assert_eq!(plain, with_emoji);
+    }
+
+    #[test]
+    fn raster_glyph_bounds_matches_what_draw_glyphs_with_raster_actually_paints() {
+        use image::{Rgba, RgbaImage};
+
+        let png_data = encode_solid_rgba_png(4, 4, [0, 255, 0, 255]);
+        let font_data = build_raster_test_font(&png_data, 1000, 16);
+        let font = FontRef::try_from_slice(&font_data).unwrap();
+
+        // Requested at 2x the strike's own pixels_per_em, so the 4x4 source
+        // bitmap should be decoded and resized up to 8x8.
+        let glyph = Glyph {
+            id: GlyphId(1),
+            scale: PxScale::from(32.0),
+            position: point(0.0, 0.0),
+        };
+
+        let bounds = raster_glyph_bounds(&font, &glyph).expect("raster glyph should have bounds");
+        assert_eq!(bounds.width(), 8.0);
+        assert_eq!(bounds.height(), 8.0);
+
+        let mut image = RgbaImage::new(20, 20);
+        draw_glyphs_with_raster(&mut image, Rgba([0u8, 0, 0, 255]), font.as_scaled(glyph.scale), vec![glyph]);
+
+        let painted = image
+            .pixels()
+            .filter(|p| p.0 == [0, 255, 0, 255])
+            .count();
+        assert_eq!(painted, 8 * 8);
+    }
+
+    #[test]
+    fn draw_glyphs_with_raster_paints_and_blends_emoji_pixels() {
+        use image::{Rgba, RgbaImage};
+
+        let png_data = encode_solid_rgba_png(2, 2, [0, 255, 0, 255]);
+        let font_data = build_raster_test_font(&png_data, 1000, 16);
+        let font = FontRef::try_from_slice(&font_data).unwrap();
+
+        // Requested at the strike's own pixels_per_em, so no resizing happens.
+        let glyph = Glyph {
+            id: GlyphId(1),
+            scale: PxScale::from(16.0),
+            position: point(5.0, 5.0),
+        };
+
+        let mut image = RgbaImage::new(20, 20);
+        draw_glyphs_with_raster(&mut image, Rgba([0u8, 0, 0, 255]), font.as_scaled(glyph.scale), vec![glyph]);
+
+        assert_eq!(*image.get_pixel(5, 5), Rgba([0, 255, 0, 255]));
+        assert_eq!(*image.get_pixel(6, 6), Rgba([0, 255, 0, 255]));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn font_fingerprint_differs_across_fonts() {
+        let regular = test_font();
+        let bold = test_font_bold();
+        assert_ne!(font_fingerprint(&regular), font_fingerprint(&bold));
+    }
+
+    #[test]
+    fn font_fingerprint_is_stable_for_the_same_font() {
+        let font = test_font();
+        assert_eq!(font_fingerprint(&font), font_fingerprint(&font));
+    }
+
+    #[test]
+    fn glyph_cache_does_not_confuse_glyphs_from_different_fonts() {
+        // Same glyph id at the same scale can rasterize to different shapes
+        // in two different fonts; a cache that ignored font identity would
+        // serve the first font's bitmap for the second font's draw call.
+        use image::{GrayImage, Luma};
+
+        let mut cache = GlyphCache::new(16);
+
+        let mut regular_image = GrayImage::new(60, 20);
+        cache.draw_text_cached_mut(
+            &mut regular_image,
+            Luma([255u8]),
+            0,
+            0,
+            PxScale::from(20.0),
+            60,
+            test_font(),
+            "W",
+        );
+
+        let mut bold_image = GrayImage::new(60, 20);
+        cache.draw_text_cached_mut(
+            &mut bold_image,
+            Luma([255u8]),
+            0,
+            0,
+            PxScale::from(20.0),
+            60,
+            test_font_bold(),
+            "W",
+        );
+
+        assert_ne!(regular_image, bold_image);
+    }
+
+    #[test]
+    fn text_size_grows_with_more_characters() {
+        let font = test_font();
+        let scale = PxScale::from(20.0);
+        let (one_char_width, _) = text_size(scale, &font, "W");
+        let (three_char_width, _) = text_size(scale, &font, "WWW");
+        assert!(three_char_width > one_char_width);
+    }
+
+    #[test]
+    fn draw_text_anchored_mut_right_align_ends_at_x() {
+        use image::{Rgba, RgbaImage};
+
+        let font = test_font();
+        let scale = PxScale::from(16.0);
+        let text = "Hi";
+        let (width, _) = text_size(scale, &font, text);
+
+        let mut image = RgbaImage::new(60, 20);
+        let x = 50u32;
+        draw_text_anchored_mut(
+            &mut image,
+            Rgba([255u8, 0, 0, 255]),
+            x,
+            0,
+            HorizontalAnchor::Right,
+            VerticalAnchor::Top,
+            scale,
+            60,
+            &font,
+            text,
+        );
+
+        let leftmost_lit_column = (0..image.width())
+            .find(|&col| (0..image.height()).any(|row| image.get_pixel(col, row).0[3] > 0))
+            .expect("text should render at least one lit pixel");
+
+        assert!(leftmost_lit_column as i32 >= x as i32 - width - 1);
+    }
+
+    fn lowest_lit_row(image: &image::RgbaImage) -> u32 {
+        (0..image.height())
+            .rev()
+            .find(|&row| (0..image.width()).any(|col| image.get_pixel(col, row).0[3] > 0))
+            .expect("text should render at least one lit pixel")
+    }
+
+    fn highest_lit_row(image: &image::RgbaImage) -> u32 {
+        (0..image.height())
+            .find(|&row| (0..image.width()).any(|col| image.get_pixel(col, row).0[3] > 0))
+            .expect("text should render at least one lit pixel")
+    }
+
+    #[test]
+    fn draw_text_anchored_mut_bottom_align_lands_ink_at_y() {
+        use image::{Rgba, RgbaImage};
+
+        let font = test_font();
+        let scale = PxScale::from(40.0);
+        let mut image = RgbaImage::new(60, 100);
+        let y = 60u32;
+        draw_text_anchored_mut(
+            &mut image,
+            Rgba([255u8, 0, 0, 255]),
+            0,
+            y,
+            HorizontalAnchor::Left,
+            VerticalAnchor::Bottom,
+            scale,
+            60,
+            &font,
+            "x",
+        );
+
+        assert!((lowest_lit_row(&image) as i32 - y as i32).abs() <= 2);
+    }
+
+    #[test]
+    fn draw_text_anchored_mut_middle_align_centers_ink_on_y() {
+        use image::{Rgba, RgbaImage};
+
+        let font = test_font();
+        let scale = PxScale::from(40.0);
+        let mut image = RgbaImage::new(60, 100);
+        let y = 50u32;
+        draw_text_anchored_mut(
+            &mut image,
+            Rgba([255u8, 0, 0, 255]),
+            0,
+            y,
+            HorizontalAnchor::Left,
+            VerticalAnchor::Middle,
+            scale,
+            60,
+            &font,
+            "x",
+        );
+
+        let center = (highest_lit_row(&image) + lowest_lit_row(&image)) as f32 / 2.0;
+        assert!((center - y as f32).abs() <= 2.0);
+    }
+
+    #[test]
+    fn layout_paragraph_wrapped_breaks_long_words_onto_a_new_line() {
+        let font = test_font();
+        let scale = PxScale::from(20.0);
+        let scaled_font = font.as_scaled(scale);
+
+        let mut glyphs = Vec::new();
+        layout_paragraph_wrapped(
+            scaled_font,
+            point(0.0, 0.0),
+            30.0,
+            1.0,
+            "a bbbbbbbbbb",
+            &mut glyphs,
+        );
+
+        // The second word doesn't fit within `max_width` on the first line,
+        // so it should have been moved down to a new line entirely.
+        let first_line_y = glyphs[0].position.y;
+        assert!(glyphs.iter().any(|g| g.position.y > first_line_y));
+    }
+
+    #[test]
+    fn layout_paragraph_wrapped_honors_explicit_newlines() {
+        let font = test_font();
+        let scale = PxScale::from(20.0);
+        let scaled_font = font.as_scaled(scale);
+
+        let mut glyphs = Vec::new();
+        layout_paragraph_wrapped(scaled_font, point(0.0, 0.0), 1000.0, 1.0, "a\nb", &mut glyphs);
+
+        assert_eq!(glyphs.len(), 2);
+        assert!(glyphs[1].position.y > glyphs[0].position.y);
+    }
+
+    #[test]
+    fn draw_text_block_mut_wrapped_text_is_taller_than_single_line() {
+        use image::{Rgba, RgbaImage};
+
+        let font = test_font();
+        let scale = PxScale::from(20.0);
+
+        let (_, single_line_bbox) = layout_text(scale, point(0.0, 0.0), &font, "a");
+
+        // The second word is wider than `max_width` on its own, so it's
+        // allowed to overflow rather than being broken mid-word; the canvas
+        // needs to be wide enough to hold it without panicking.
+        let mut image = RgbaImage::new(300, 100);
+        let block_bbox = draw_text_block_mut(
+            &mut image,
+            Rgba([255u8, 0, 0, 255]),
+            0,
+            0,
+            scale,
+            30,
+            TextBlockOptions::default(),
+            &font,
+            "a bbbbbbbbbb",
+        );
+
+        assert!(block_bbox.height() > single_line_bbox.height());
+    }
+}